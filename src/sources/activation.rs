@@ -0,0 +1,14 @@
+//! Shared tuning knobs for the logging demux operators.
+
+/// Default number of replayed log batches after which a logging demux
+/// eagerly re-activates itself, rather than waiting for the next
+/// introspection interval. Chosen to bound memory at roughly 8KiB per
+/// outstanding batch. Each logging source's `activation_batch_threshold`
+/// field defaults to this, but can be tuned per source to trade memory
+/// for latency.
+pub(crate) const DEFAULT_BATCH_ACTIVATION_THRESHOLD: usize = 32;
+
+/// `serde(default = ...)` hook for `activation_batch_threshold` fields.
+pub(crate) fn default_activation_batch_threshold() -> usize {
+    DEFAULT_BATCH_ACTIVATION_THRESHOLD
+}
@@ -12,16 +12,36 @@ use timely::dataflow::{Scope, Stream};
 
 use differential_dataflow::logging::DifferentialEvent;
 
+use crate::sources::activation::default_activation_batch_threshold;
+use crate::sources::region::TupleBuffer;
 use crate::sources::{Sourceable, SourcingContext};
 use crate::{AsAid, Value};
 use crate::{AttributeConfig, InputSemantics};
 use Value::{Eid, Number};
 
+/// Per-attribute `(size, records, batches)` deltas produced by a single
+/// `Batch` event of the given `length`.
+fn batch_deltas(length: i64) -> (i64, i64, i64) {
+    (length, length, 1)
+}
+
+/// Per-attribute `(size, records, batches)` deltas produced by a
+/// completed `Merge`, given the net change in spine size.
+fn merge_deltas(size_diff: i64) -> (i64, i64, i64) {
+    (size_diff, size_diff, -1)
+}
+
 /// One or more taps into Differential logging.
 #[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
 pub struct DifferentialLogging<A: AsAid> {
     /// The log attributes that should be materialized.
     pub attributes: Vec<A>,
+    /// Number of replayed log batches after which the demux eagerly
+    /// re-activates itself, rather than waiting for the next
+    /// introspection interval. Tune this down for lower latency at the
+    /// cost of more frequent activations, or up to the reverse.
+    #[serde(default = "default_activation_batch_threshold")]
+    pub activation_batch_threshold: usize,
 }
 
 impl<A, S> Sourceable<A, S> for DifferentialLogging<A>
@@ -58,8 +78,16 @@ where
         let num_interests = self.attributes.len();
 
         let size = A::from("differential.event/size");
+        let records = A::from("differential.event/records");
+        let batches = A::from("differential.event/batches");
+        let sharing = A::from("differential.event/sharing");
+
+        let activator = scope.activator_for(&demux.operator_info().address);
+        let activation_batch_threshold = self.activation_batch_threshold;
 
         demux.build(move |_capability| {
+            let mut batches_since_activation = 0;
+
             move |_frontiers| {
                 let mut handles = HashMap::with_capacity(num_interests);
                 for (aid, wrapper) in wrappers.iter_mut() {
@@ -69,20 +97,44 @@ where
                 input.for_each(|time, data: RefOrMut<Vec<_>>| {
                     data.swap(&mut demux_buffer);
 
+                    batches_since_activation += 1;
+                    if batches_since_activation >= activation_batch_threshold {
+                        batches_since_activation = 0;
+                        activator.activate();
+                    }
+
                     let mut sessions = HashMap::with_capacity(num_interests);
                     for (aid, handle) in handles.iter_mut() {
                         sessions.insert(aid.clone(), handle.session(&time));
                     }
 
+                    let mut buffers: HashMap<A, TupleBuffer> =
+                        HashMap::with_capacity(num_interests);
+
                     for (time, _worker, datum) in demux_buffer.drain(..) {
                         match datum {
                             DifferentialEvent::Batch(x) => {
                                 let operator = Eid((x.operator as u64).into());
-                                let length = Number(x.length as i64);
-
-                                sessions
-                                    .get_mut(&size)
-                                    .map(|s| s.give(((operator, length), time, 1)));
+                                let (size_delta, records_delta, batches_delta) =
+                                    batch_deltas(x.length as i64);
+
+                                buffers.entry(size.clone()).or_default().push(
+                                    (operator.clone(), Number(size_delta)),
+                                    time,
+                                    1,
+                                );
+
+                                buffers.entry(records.clone()).or_default().push(
+                                    (operator.clone(), Number(records_delta)),
+                                    time,
+                                    1,
+                                );
+
+                                buffers.entry(batches.clone()).or_default().push(
+                                    (operator, Number(batches_delta)),
+                                    time,
+                                    1,
+                                );
                             }
                             DifferentialEvent::Merge(x) => {
                                 trace!("[DIFFERENTIAL] {:?}", x);
@@ -91,15 +143,60 @@ where
                                     let operator = Eid((x.operator as u64).into());
                                     let size_diff =
                                         (complete_size as i64) - (x.length1 + x.length2) as i64;
-
-                                    sessions
-                                        .get_mut(&size)
-                                        .map(|s| s.give(((operator, Number(size_diff)), time, 1)));
+                                    let (size_delta, records_delta, batches_delta) =
+                                        merge_deltas(size_diff);
+
+                                    buffers.entry(size.clone()).or_default().push(
+                                        (operator.clone(), Number(size_delta)),
+                                        time,
+                                        1,
+                                    );
+
+                                    buffers.entry(records.clone()).or_default().push(
+                                        (operator.clone(), Number(records_delta)),
+                                        time,
+                                        1,
+                                    );
+
+                                    buffers.entry(batches.clone()).or_default().push(
+                                        (operator, Number(batches_delta)),
+                                        time,
+                                        1,
+                                    );
                                 }
                             }
+                            DifferentialEvent::Drop(x) => {
+                                let operator = Eid((x.operator as u64).into());
+                                let length = Number(-(x.length as i64));
+
+                                buffers
+                                    .entry(records.clone())
+                                    .or_default()
+                                    .push((operator, length), time, 1);
+                            }
+                            DifferentialEvent::TraceShare(x) => {
+                                let operator = Eid((x.operator as u64).into());
+                                let diff = Number(x.diff as i64);
+
+                                buffers
+                                    .entry(sharing.clone())
+                                    .or_default()
+                                    .push((operator, diff), time, 1);
+                            }
+                            DifferentialEvent::MergeShortfall(x) => {
+                                trace!("[DIFFERENTIAL] {:?}", x);
+                            }
                             _ => {}
                         }
                     }
+
+                    for (aid, buffer) in buffers.iter_mut() {
+                        if let Some(session) = sessions.get_mut(aid) {
+                            for tuple in buffer.drain() {
+                                session.give(tuple);
+                            }
+                        }
+                    }
                 });
             }
         });
@@ -116,3 +213,43 @@ where
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{batch_deltas, merge_deltas};
+
+    #[test]
+    fn batch_deltas_increments_size_records_and_batch_count() {
+        assert_eq!(batch_deltas(7), (7, 7, 1));
+    }
+
+    #[test]
+    fn merge_deltas_adjusts_size_and_records_by_shortfall_and_decrements_batches() {
+        // A merge of two batches of length 5 into a combined batch of 8
+        // records frees 2 records (5 + 5 - 8) and removes one batch.
+        assert_eq!(merge_deltas(-2), (-2, -2, -1));
+    }
+
+    #[test]
+    fn merge_deltas_can_grow_records() {
+        // A completed merge whose output holds more records than its
+        // inputs summed to still decrements the batch count.
+        assert_eq!(merge_deltas(3), (3, 3, -1));
+    }
+
+    #[test]
+    fn sequence_of_batch_merge_drop_nets_out_records() {
+        // Two batches of 4 records each, merged down to 6 (a shortfall
+        // of 2), then 3 records dropped, should leave 3 live records.
+        let mut records = 0;
+        let (_, delta, _) = batch_deltas(4);
+        records += delta;
+        let (_, delta, _) = batch_deltas(4);
+        records += delta;
+        let (_, delta, _) = merge_deltas(6 - (4 + 4));
+        records += delta;
+        records += -3; // Drop(length = 3)
+
+        assert_eq!(records, 3);
+    }
+}
@@ -0,0 +1,195 @@
+//! On-demand profiling of the logging attributes materialized by
+//! [`DifferentialLogging`](super::differential_logging::DifferentialLogging)
+//! and [`TimelyLogging`](super::timely_logging::TimelyLogging).
+//!
+//! `Profile` is a control command, analogous to a dump/timestamp
+//! command: rather than tapping external tooling, it reads the durable
+//! arrangements backing `differential.event/size`,
+//! `differential.event/records`, `differential.event/batches` and, if
+//! timely logging is enabled, `timely.event/schedule-duration`, and
+//! joins them by operator `Eid` into a single per-operator report.
+//!
+//! [`arrange_outputs`] is what actually materializes a logging source's
+//! raw output streams into the durable traces `Profile::execute` reads
+//! back; without it there is nothing for a profile request to probe.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use differential_dataflow::collection::AsCollection;
+use differential_dataflow::operators::arrange::{ArrangeByKey, TraceAgent};
+use differential_dataflow::trace::cursor::Cursor;
+use differential_dataflow::trace::implementations::ord::OrdValSpine;
+use differential_dataflow::trace::TraceReader;
+use timely::dataflow::{Scope, Stream};
+
+use crate::{AsAid, AttributeConfig, Value};
+use Value::Number;
+
+/// A durable, synchronously-probeable trace for one logging attribute.
+pub type AttributeTrace<S> =
+    TraceAgent<OrdValSpine<Value, Value, <S as Scope>::Timestamp, isize>>;
+
+/// Arranges a logging source's `(aid, config, stream)` outputs — as
+/// returned by [`Sourceable::source`](super::Sourceable::source) — into
+/// durable per-attribute traces keyed by attribute id. Dispatching
+/// [`Profile::execute`] against the result is how a profile request is
+/// actually served, rather than against the raw, fly-by streams.
+pub fn arrange_outputs<A, S>(
+    outputs: Vec<(
+        A,
+        AttributeConfig,
+        Stream<S, ((Value, Value), Duration, isize)>,
+    )>,
+) -> HashMap<A, AttributeTrace<S>>
+where
+    A: AsAid,
+    S: Scope<Timestamp = Duration>,
+{
+    outputs
+        .into_iter()
+        .map(|(aid, _config, stream)| (aid, stream.as_collection().arrange_by_key().trace))
+        .collect()
+}
+
+/// A snapshot of the logging attributes for a single operator.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperatorProfile {
+    /// Number of live records in this operator's arrangement.
+    pub records: i64,
+    /// Number of batches currently held by this operator's arrangement.
+    pub batches: i64,
+    /// Net spine size of this operator's arrangement.
+    pub size: i64,
+    /// Accumulated scheduling time, in nanoseconds, if timely logging
+    /// is enabled for this dataflow.
+    pub schedule_nanos: Option<i64>,
+}
+
+/// A request to snapshot the currently materialized logging attributes
+/// into a per-operator profile, dispatched like the crate's other
+/// control commands (e.g. dump, timestamp).
+#[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+pub struct Profile;
+
+impl Profile {
+    /// Reads `differential.event/size`, `.../records`, `.../batches` and,
+    /// if present, `timely.event/schedule-duration` out of `traces` and
+    /// joins them by operator `Eid` into one report per operator.
+    ///
+    /// Each trace is read via its cursor over its current contents, so
+    /// this is a point-in-time snapshot rather than a subscription.
+    /// Attributes missing from `traces` (e.g. because timely logging
+    /// isn't enabled) are simply skipped.
+    pub fn execute<A, S>(
+        &self,
+        traces: &mut HashMap<A, AttributeTrace<S>>,
+    ) -> HashMap<Value, OperatorProfile>
+    where
+        A: AsAid + From<&'static str>,
+        S: Scope<Timestamp = Duration>,
+    {
+        let mut report: HashMap<Value, OperatorProfile> = HashMap::new();
+
+        if let Some(trace) = traces.get_mut(&A::from("differential.event/size")) {
+            accumulate(trace, &mut report, |profile, delta| profile.size += delta);
+        }
+        if let Some(trace) = traces.get_mut(&A::from("differential.event/records")) {
+            accumulate(trace, &mut report, |profile, delta| {
+                profile.records += delta
+            });
+        }
+        if let Some(trace) = traces.get_mut(&A::from("differential.event/batches")) {
+            accumulate(trace, &mut report, |profile, delta| {
+                profile.batches += delta
+            });
+        }
+        if let Some(trace) = traces.get_mut(&A::from("timely.event/schedule-duration")) {
+            accumulate(trace, &mut report, |profile, delta| {
+                *profile.schedule_nanos.get_or_insert(0) += delta;
+            });
+        }
+
+        report
+    }
+}
+
+/// Reads a trace's current contents into `report`, applying `apply` to
+/// each operator with its value weighted by its accumulated multiplicity.
+fn accumulate<T>(
+    trace: &mut T,
+    report: &mut HashMap<Value, OperatorProfile>,
+    apply: impl Fn(&mut OperatorProfile, i64),
+) where
+    T: TraceReader<Key = Value, Val = Value, Time = Duration, R = isize>,
+{
+    let (mut cursor, storage) = trace.cursor();
+
+    while cursor.key_valid(&storage) {
+        let operator = cursor.key(&storage).clone();
+
+        while cursor.val_valid(&storage) {
+            let value = cursor.val(&storage);
+
+            let mut weight = 0isize;
+            cursor.map_times(&storage, |_time, diff| weight += diff);
+
+            if weight != 0 {
+                if let Some(delta) = weighted_delta(value, weight) {
+                    apply(report.entry(operator.clone()).or_default(), delta);
+                }
+            }
+
+            cursor.step_val(&storage);
+        }
+
+        cursor.step_key(&storage);
+    }
+}
+
+/// Computes the signed contribution of a single arrangement entry —
+/// `value` weighted by its accumulated multiplicity `weight` — to an
+/// aggregate `i64` delta. Non-numeric values contribute nothing, since
+/// every attribute profiled here is a bare count or duration.
+fn weighted_delta(value: &Value, weight: isize) -> Option<i64> {
+    match value {
+        Number(number) => Some(*number * weight as i64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{weighted_delta, OperatorProfile};
+    use crate::Value;
+
+    #[test]
+    fn numeric_value_scaled_by_weight() {
+        assert_eq!(weighted_delta(&Value::Number(5), 3), Some(15));
+        assert_eq!(weighted_delta(&Value::Number(5), -2), Some(-10));
+    }
+
+    #[test]
+    fn non_numeric_value_contributes_nothing() {
+        assert_eq!(weighted_delta(&Value::Eid(1u64.into()), 4), None);
+    }
+
+    #[test]
+    fn operator_profile_accumulates_independent_fields() {
+        let mut profile = OperatorProfile::default();
+        profile.size += weighted_delta(&Value::Number(10), 1).unwrap();
+        profile.records += weighted_delta(&Value::Number(10), 1).unwrap();
+        profile.batches += weighted_delta(&Value::Number(1), 1).unwrap();
+        profile.records += weighted_delta(&Value::Number(3), -1).unwrap();
+
+        assert_eq!(
+            profile,
+            OperatorProfile {
+                size: 10,
+                records: 7,
+                batches: 1,
+                schedule_nanos: None,
+            }
+        );
+    }
+}
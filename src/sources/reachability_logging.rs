@@ -0,0 +1,168 @@
+//! Operator and utilities to source data from the underlying
+//! Timely reachability logging stream.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use timely::communication::message::RefOrMut;
+use timely::dataflow::channels::pact::Pipeline;
+use timely::dataflow::operators::capture::Replay;
+use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
+use timely::dataflow::{Scope, Stream};
+use timely::logging::TimelyProgressEvent;
+
+use crate::sources::activation::default_activation_batch_threshold;
+use crate::sources::region::TupleBuffer;
+use crate::sources::{Sourceable, SourcingContext};
+use crate::{AsAid, Value};
+use crate::{AttributeConfig, InputSemantics};
+use Value::{Eid, Number};
+
+/// One or more taps into Timely's reachability logging.
+#[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+pub struct ReachabilityLogging<A: AsAid> {
+    /// The log attributes that should be materialized.
+    pub attributes: Vec<A>,
+    /// Number of replayed log batches after which the demux eagerly
+    /// re-activates itself, rather than waiting for the next
+    /// introspection interval. Tune this down for lower latency at the
+    /// cost of more frequent activations, or up to the reverse.
+    #[serde(default = "default_activation_batch_threshold")]
+    pub activation_batch_threshold: usize,
+}
+
+impl<A, S> Sourceable<A, S> for ReachabilityLogging<A>
+where
+    A: AsAid + From<&'static str>,
+    S: Scope<Timestamp = Duration>,
+{
+    fn source(
+        &self,
+        scope: &mut S,
+        context: SourcingContext<S::Timestamp>,
+    ) -> Vec<(
+        A,
+        AttributeConfig,
+        Stream<S, ((Value, Value), Duration, isize)>,
+    )> {
+        let input = Some(context.reachability_events).replay_into(scope);
+
+        let mut demux =
+            OperatorBuilder::new("Reachability Logging Demux".to_string(), scope.clone());
+
+        let mut input = demux.new_input(&input, Pipeline);
+
+        let mut wrappers = HashMap::with_capacity(self.attributes.len());
+        let mut streams = HashMap::with_capacity(self.attributes.len());
+
+        for aid in self.attributes.iter() {
+            let (wrapper, stream) = demux.new_output();
+            wrappers.insert(aid.clone(), wrapper);
+            streams.insert(aid.clone(), stream);
+        }
+
+        let mut demux_buffer = Vec::new();
+        let num_interests = self.attributes.len();
+
+        let source_updates = A::from("reachability.event/source");
+        let target_updates = A::from("reachability.event/target");
+
+        let activator = scope.activator_for(&demux.operator_info().address);
+        let activation_batch_threshold = self.activation_batch_threshold;
+
+        demux.build(move |_capability| {
+            let mut batches_since_activation = 0;
+
+            move |_frontiers| {
+                let mut handles = HashMap::with_capacity(num_interests);
+                for (aid, wrapper) in wrappers.iter_mut() {
+                    handles.insert(aid.clone(), wrapper.activate());
+                }
+
+                input.for_each(|cap_time, data: RefOrMut<Vec<_>>| {
+                    data.swap(&mut demux_buffer);
+
+                    batches_since_activation += 1;
+                    if batches_since_activation >= activation_batch_threshold {
+                        batches_since_activation = 0;
+                        activator.activate();
+                    }
+
+                    let mut sessions = HashMap::with_capacity(num_interests);
+                    for (aid, handle) in handles.iter_mut() {
+                        sessions.insert(aid.clone(), handle.session(&cap_time));
+                    }
+
+                    let mut buffers: HashMap<A, TupleBuffer> =
+                        HashMap::with_capacity(num_interests);
+
+                    for (_ts, _worker, event) in demux_buffer.drain(..) {
+                        let TimelyProgressEvent {
+                            is_send: _,
+                            source,
+                            channel: _,
+                            seq_no: _,
+                            addr,
+                            messages,
+                        } = event;
+
+                        // `TimelyProgressEvent` has no literal "target"
+                        // field: `source` is the endpoint that sent or
+                        // received this pointstamp update, and `addr` is
+                        // the dataflow address of the operator the update
+                        // concerns. We take the last component of `addr`
+                        // as that operator, so both ends of the update —
+                        // the endpoint and the operator it propagates
+                        // through — are captured instead of only `source`.
+                        let source_operator = Eid((source as u64).into());
+                        let target_operator =
+                            Eid((*addr.last().unwrap_or(&source) as u64).into());
+
+                        trace!(
+                            "[REACHABILITY] {:?} -> {:?}",
+                            source_operator, target_operator
+                        );
+
+                        for (port, update_time, diff) in messages {
+                            buffers
+                                .entry(source_updates.clone())
+                                .or_default()
+                                .push(
+                                    (source_operator.clone(), Number(port as i64)),
+                                    update_time,
+                                    diff as isize,
+                                );
+                            buffers
+                                .entry(target_updates.clone())
+                                .or_default()
+                                .push(
+                                    (target_operator.clone(), Number(port as i64)),
+                                    update_time,
+                                    diff as isize,
+                                );
+                        }
+                    }
+
+                    for (aid, buffer) in buffers.iter_mut() {
+                        if let Some(session) = sessions.get_mut(aid) {
+                            for tuple in buffer.drain() {
+                                session.give(tuple);
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        self.attributes
+            .iter()
+            .map(|aid| {
+                (
+                    aid.clone(),
+                    AttributeConfig::real_time(InputSemantics::Raw),
+                    streams.remove(aid).unwrap(),
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,263 @@
+//! Columnar, region-backed staging buffers for the logging demux
+//! output sessions.
+//!
+//! Calling `session.give(...)` once per logged event allocates at least
+//! once per tuple (the `Value` payload, in the worst case, boxes a
+//! `String`). Under high-volume logging this dominates allocation
+//! pressure relative to the actual work being done. `TupleBuffer` stages
+//! `((Value, Value), Duration, isize)` tuples into a [`FlatStack`] of
+//! per-field regions so that many tuples share a handful of contiguous
+//! arenas instead of each owning their own heap cells, then exposes a
+//! `drain` that hands the staged tuples to the timely session exactly as
+//! before.
+
+use std::time::Duration;
+
+use flatcontainer::{FlatStack, Push, Region};
+
+use crate::Value;
+
+/// Region backing for our `Value` enum.
+///
+/// The hot logging paths only ever produce [`Value::Eid`], [`Value::Number`]
+/// and [`Value::String`] payloads, so those are flattened into dedicated
+/// columns. Any other variant is staged into a fallback column of owned
+/// `Value`s, which still avoids the per-tuple `Vec` growth of a naive
+/// `Vec<((Value, Value), Duration, isize)>` buffer, just without the
+/// columnar win for that column.
+#[derive(Default, Debug)]
+pub struct ValueRegion {
+    tags: Vec<ValueTag>,
+    /// Index into the column named by the matching entry of `tags`,
+    /// recorded at push time so `index()` is O(1) instead of rescanning
+    /// `tags` for the n-th occurrence of a variant.
+    offsets: Vec<usize>,
+    eids: Vec<u64>,
+    numbers: Vec<i64>,
+    strings: Vec<String>,
+    other: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ValueTag {
+    Eid,
+    Number,
+    String,
+    Other,
+}
+
+impl Region for ValueRegion {
+    type Owned = Value;
+    type ReadItem<'a> = Value;
+    type Index = usize;
+
+    fn merge_regions<'a>(regions: impl Iterator<Item = &'a Self> + Clone) -> Self
+    where
+        Self: 'a,
+    {
+        let mut region = Self::default();
+        for other in regions {
+            let eid_base = region.eids.len();
+            let number_base = region.numbers.len();
+            let string_base = region.strings.len();
+            let other_base = region.other.len();
+
+            for (&tag, &offset) in other.tags.iter().zip(other.offsets.iter()) {
+                region.tags.push(tag);
+                region.offsets.push(
+                    offset
+                        + match tag {
+                            ValueTag::Eid => eid_base,
+                            ValueTag::Number => number_base,
+                            ValueTag::String => string_base,
+                            ValueTag::Other => other_base,
+                        },
+                );
+            }
+
+            region.eids.extend(other.eids.iter().copied());
+            region.numbers.extend(other.numbers.iter().copied());
+            region.strings.extend(other.strings.iter().cloned());
+            region.other.extend(other.other.iter().cloned());
+        }
+        region
+    }
+
+    fn index(&self, index: Self::Index) -> Self::ReadItem<'_> {
+        let offset = self.offsets[index];
+        match self.tags[index] {
+            ValueTag::Eid => Value::Eid(self.eids[offset].into()),
+            ValueTag::Number => Value::Number(self.numbers[offset]),
+            ValueTag::String => Value::String(self.strings[offset].clone()),
+            ValueTag::Other => self.other[offset].clone(),
+        }
+    }
+
+    fn reserve_items<'a, I>(&mut self, items: I)
+    where
+        Self: 'a,
+        I: Iterator<Item = &'a Self::Owned> + Clone,
+    {
+        // We don't know the variant breakdown ahead of time, so reserve
+        // every column for the worst case (all items landing in one
+        // column) rather than leaving them to reallocate as data streams
+        // in.
+        let additional = items.size_hint().0;
+        self.tags.reserve(additional);
+        self.offsets.reserve(additional);
+        self.eids.reserve(additional);
+        self.numbers.reserve(additional);
+        self.strings.reserve(additional);
+        self.other.reserve(additional);
+    }
+
+    fn reserve_regions<'a, I>(&mut self, _regions: I)
+    where
+        Self: 'a,
+        I: Iterator<Item = &'a Self> + Clone,
+    {
+    }
+
+    fn clear(&mut self) {
+        self.tags.clear();
+        self.offsets.clear();
+        self.eids.clear();
+        self.numbers.clear();
+        self.strings.clear();
+        self.other.clear();
+    }
+}
+
+impl Push<Value> for ValueRegion {
+    fn push(&mut self, item: Value) -> Self::Index {
+        let index = self.tags.len();
+        match item {
+            Value::Eid(eid) => {
+                self.offsets.push(self.eids.len());
+                self.tags.push(ValueTag::Eid);
+                self.eids.push(eid.into());
+            }
+            Value::Number(number) => {
+                self.offsets.push(self.numbers.len());
+                self.tags.push(ValueTag::Number);
+                self.numbers.push(number);
+            }
+            Value::String(string) => {
+                self.offsets.push(self.strings.len());
+                self.tags.push(ValueTag::String);
+                self.strings.push(string);
+            }
+            other => {
+                self.offsets.push(self.other.len());
+                self.tags.push(ValueTag::Other);
+                self.other.push(other);
+            }
+        }
+        index
+    }
+}
+
+/// A staging buffer for the `((Value, Value), Duration, isize)` tuples a
+/// logging demux session emits, backed by per-field regions instead of a
+/// `Vec` of owned tuples.
+pub struct TupleBuffer {
+    keys: FlatStack<(ValueRegion, ValueRegion)>,
+    times: Vec<Duration>,
+    diffs: Vec<isize>,
+}
+
+impl TupleBuffer {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        Self {
+            keys: FlatStack::default(),
+            times: Vec::new(),
+            diffs: Vec::new(),
+        }
+    }
+
+    /// Copies `((key0, key1), time, diff)` into the region-backed columns.
+    pub fn push(&mut self, key: (Value, Value), time: Duration, diff: isize) {
+        self.keys.copy(&key);
+        self.times.push(time);
+        self.diffs.push(diff);
+    }
+
+    /// Drains the buffer, presenting the staged tuples as the owned-tuple
+    /// iterator a `Session::give` loop expects.
+    pub fn drain(&mut self) -> impl Iterator<Item = ((Value, Value), Duration, isize)> + '_ {
+        self.keys
+            .drain()
+            .zip(self.times.drain(..))
+            .zip(self.diffs.drain(..))
+            .map(|((key, time), diff)| (key, time, diff))
+    }
+
+    /// Whether any tuples are currently staged.
+    pub fn is_empty(&self) -> bool {
+        self.diffs.is_empty()
+    }
+}
+
+impl Default for TupleBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TupleBuffer;
+    use crate::Value;
+    use std::time::Duration;
+    use Value::{Eid, Number, String as Str};
+
+    #[test]
+    fn round_trips_mixed_variant_tuples_in_push_order() {
+        let mut buffer = TupleBuffer::new();
+
+        let tuples = vec![
+            ((Eid(1u64.into()), Number(42)), Duration::from_secs(1), 1isize),
+            (
+                (Eid(2u64.into()), Str("arrangement".to_string())),
+                Duration::from_secs(2),
+                -1,
+            ),
+            ((Number(7), Eid(3u64.into())), Duration::from_secs(3), 2),
+            ((Eid(1u64.into()), Number(43)), Duration::from_secs(4), 1),
+        ];
+
+        for (key, time, diff) in tuples.clone() {
+            buffer.push(key, time, diff);
+        }
+
+        assert!(!buffer.is_empty());
+        let drained: Vec<_> = buffer.drain().collect();
+        assert_eq!(drained, tuples);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn index_recovers_nth_occurrence_of_each_variant() {
+        // Interleaving many values of the same variant with values of
+        // other variants used to force an O(n) rescan per `index()` call;
+        // this exercises that the per-push offset bookkeeping still
+        // resolves to the correct value for a large, mixed sequence.
+        let mut buffer = TupleBuffer::new();
+        let mut expected = Vec::new();
+
+        for i in 0..256u64 {
+            let key = if i % 2 == 0 {
+                (Eid(i.into()), Number(i as i64))
+            } else {
+                (Number(i as i64), Eid(i.into()))
+            };
+            let time = Duration::from_millis(i);
+            buffer.push(key.clone(), time, 1);
+            expected.push((key, time, 1isize));
+        }
+
+        let drained: Vec<_> = buffer.drain().collect();
+        assert_eq!(drained, expected);
+    }
+}
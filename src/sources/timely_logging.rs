@@ -0,0 +1,193 @@
+//! Operator and utilities to source data from the underlying
+//! Timely logging streams.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use timely::communication::message::RefOrMut;
+use timely::dataflow::channels::pact::Pipeline;
+use timely::dataflow::operators::capture::Replay;
+use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
+use timely::dataflow::{Scope, Stream};
+use timely::logging::TimelyEvent;
+
+use crate::sources::activation::default_activation_batch_threshold;
+use crate::sources::region::TupleBuffer;
+use crate::sources::{Sourceable, SourcingContext};
+use crate::{AsAid, Value};
+use crate::{AttributeConfig, InputSemantics};
+use Value::{Eid, Number, String as Str};
+
+/// One or more taps into Timely logging.
+#[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+pub struct TimelyLogging<A: AsAid> {
+    /// The log attributes that should be materialized.
+    pub attributes: Vec<A>,
+    /// Number of replayed log batches after which the demux eagerly
+    /// re-activates itself, rather than waiting for the next
+    /// introspection interval. Tune this down for lower latency at the
+    /// cost of more frequent activations, or up to the reverse.
+    #[serde(default = "default_activation_batch_threshold")]
+    pub activation_batch_threshold: usize,
+}
+
+impl<A, S> Sourceable<A, S> for TimelyLogging<A>
+where
+    A: AsAid + From<&'static str>,
+    S: Scope<Timestamp = Duration>,
+{
+    fn source(
+        &self,
+        scope: &mut S,
+        context: SourcingContext<S::Timestamp>,
+    ) -> Vec<(
+        A,
+        AttributeConfig,
+        Stream<S, ((Value, Value), Duration, isize)>,
+    )> {
+        let input = Some(context.timely_events).replay_into(scope);
+
+        let mut demux = OperatorBuilder::new("Timely Logging Demux".to_string(), scope.clone());
+
+        let mut input = demux.new_input(&input, Pipeline);
+
+        let mut wrappers = HashMap::with_capacity(self.attributes.len());
+        let mut streams = HashMap::with_capacity(self.attributes.len());
+
+        for aid in self.attributes.iter() {
+            let (wrapper, stream) = demux.new_output();
+            wrappers.insert(aid.clone(), wrapper);
+            streams.insert(aid.clone(), stream);
+        }
+
+        let mut demux_buffer = Vec::new();
+        let num_interests = self.attributes.len();
+
+        let operates = A::from("timely.event/operates");
+        let channel_source = A::from("timely.event/channel-source");
+        let channel_target = A::from("timely.event/channel-target");
+        let schedule_duration = A::from("timely.event/schedule-duration");
+        let messages = A::from("timely.event/messages");
+
+        let activator = scope.activator_for(&demux.operator_info().address);
+        let activation_batch_threshold = self.activation_batch_threshold;
+
+        demux.build(move |_capability| {
+            // Start times of operators currently being scheduled, by operator id.
+            let mut scheduled_since = HashMap::new();
+            let mut batches_since_activation = 0;
+
+            move |_frontiers| {
+                let mut handles = HashMap::with_capacity(num_interests);
+                for (aid, wrapper) in wrappers.iter_mut() {
+                    handles.insert(aid.clone(), wrapper.activate());
+                }
+
+                input.for_each(|time, data: RefOrMut<Vec<_>>| {
+                    data.swap(&mut demux_buffer);
+
+                    batches_since_activation += 1;
+                    if batches_since_activation >= activation_batch_threshold {
+                        batches_since_activation = 0;
+                        activator.activate();
+                    }
+
+                    let mut sessions = HashMap::with_capacity(num_interests);
+                    for (aid, handle) in handles.iter_mut() {
+                        sessions.insert(aid.clone(), handle.session(&time));
+                    }
+
+                    let mut buffers: HashMap<A, TupleBuffer> =
+                        HashMap::with_capacity(num_interests);
+
+                    for (ts, _worker, datum) in demux_buffer.drain(..) {
+                        match datum {
+                            TimelyEvent::Operates(x) => {
+                                let operator = Eid((x.id as u64).into());
+                                let name = Str(x.name.into());
+
+                                buffers
+                                    .entry(operates.clone())
+                                    .or_default()
+                                    .push((operator, name), ts, 1);
+                            }
+                            TimelyEvent::Channels(x) => {
+                                let channel = Eid((x.id as u64).into());
+                                let from_op = Eid((x.source.0 as u64).into());
+                                let to_op = Eid((x.target.0 as u64).into());
+
+                                // Kept as two distinctly-named attributes
+                                // rather than two facts under one attribute,
+                                // so a query can tell source from target
+                                // instead of getting an ambiguous pair of
+                                // operators per channel.
+                                buffers
+                                    .entry(channel_source.clone())
+                                    .or_default()
+                                    .push((channel.clone(), from_op), ts, 1);
+                                buffers
+                                    .entry(channel_target.clone())
+                                    .or_default()
+                                    .push((channel, to_op), ts, 1);
+                            }
+                            TimelyEvent::Schedule(x) => {
+                                let operator = Eid((x.id as u64).into());
+
+                                match x.start_stop {
+                                    timely::logging::StartStop::Start => {
+                                        scheduled_since.insert(x.id, ts);
+                                    }
+                                    timely::logging::StartStop::Stop => {
+                                        if let Some(start) = scheduled_since.remove(&x.id) {
+                                            let elapsed = ts.saturating_sub(start);
+                                            let nanos = Number(elapsed.as_nanos() as i64);
+
+                                            buffers
+                                                .entry(schedule_duration.clone())
+                                                .or_default()
+                                                .push((operator, nanos), ts, 1);
+                                        }
+                                    }
+                                }
+                            }
+                            TimelyEvent::Messages(x) => {
+                                // `MessagesEvent` is logged once on send and
+                                // once on receive; only count the send side
+                                // so each logical message is counted once.
+                                if x.is_send {
+                                    let channel = Eid((x.channel as u64).into());
+                                    let count = Number(x.length as i64);
+
+                                    buffers
+                                        .entry(messages.clone())
+                                        .or_default()
+                                        .push((channel, count), ts, 1);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    for (aid, buffer) in buffers.iter_mut() {
+                        if let Some(session) = sessions.get_mut(aid) {
+                            for tuple in buffer.drain() {
+                                session.give(tuple);
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        self.attributes
+            .iter()
+            .map(|aid| {
+                (
+                    aid.clone(),
+                    AttributeConfig::real_time(InputSemantics::Raw),
+                    streams.remove(aid).unwrap(),
+                )
+            })
+            .collect()
+    }
+}